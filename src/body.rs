@@ -4,6 +4,7 @@ use mime::{self, Mime};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use serde_json;
+use serde_urlencoded;
 use std::ops::Deref;
 
 pub trait RequestBody {
@@ -15,6 +16,22 @@ pub trait RequestBody {
 pub trait ResponseBody: Sized {
     fn accept_types() -> &'static str;
 
+    /// Returns whether the response's `Content-Type` is acceptable for this
+    /// type, based on `accept_types()`. A `*/*` acceptor always matches;
+    /// otherwise the header must be present and match by essence, ignoring
+    /// parameters such as `charset`. A missing header is treated as a
+    /// mismatch so that header-less error pages (which this check exists to
+    /// catch) don't slip through to `from_bytes`.
+    fn content_type_matches(mime: Option<&Mime>) -> bool {
+        if Self::accept_types() == "*/*" {
+            return true;
+        }
+        match mime {
+            Some(m) => m.essence_str() == Self::accept_types(),
+            None => false,
+        }
+    }
+
     fn from_bytes(status: StatusCode, body: Vec<u8>) -> Result<Self, HError>;
 }
 
@@ -49,13 +66,7 @@ impl<V> Json<V> {
 }
 
 pub fn decode_json<T: DeserializeOwned>(slice: &[u8]) -> Result<T, HError> {
-    match serde_json::from_slice(slice) {
-        Ok(v) => Ok(v),
-        Err(e) => Err(HError::InvalidDataFormat(format!(
-            "invalid data format: {}",
-            e
-        ))),
-    }
+    Ok(serde_json::from_slice(slice)?)
 }
 
 impl<'a, V> RequestBody for Json<V>
@@ -65,10 +76,7 @@ where
     const MIME: Mime = mime::APPLICATION_JSON;
 
     fn to_bytes(self) -> Result<Vec<u8>, HError> {
-        match serde_json::to_vec(&self.0) {
-            Ok(v) => Ok(v),
-            Err(e) => Err(HError::InvalidDataFormat(format!("{}", e))),
-        }
+        Ok(serde_json::to_vec(&self.0)?)
     }
 }
 
@@ -90,6 +98,47 @@ impl<V: Send> Deref for Json<V> {
     }
 }
 
+pub struct Form<V>(pub V);
+
+impl<V> Form<V> {
+    pub fn into_inner(self) -> V {
+        self.0
+    }
+
+    pub fn inner(&self) -> &V {
+        &self.0
+    }
+}
+
+impl<V> RequestBody for Form<V>
+where
+    V: Serialize,
+{
+    const MIME: Mime = mime::APPLICATION_WWW_FORM_URLENCODED;
+
+    fn to_bytes(self) -> Result<Vec<u8>, HError> {
+        Ok(serde_urlencoded::to_string(&self.0)?.into_bytes())
+    }
+}
+
+impl<V: DeserializeOwned> ResponseBody for Form<V> {
+    fn accept_types() -> &'static str {
+        "application/x-www-form-urlencoded"
+    }
+
+    fn from_bytes(_: StatusCode, body: Vec<u8>) -> Result<Self, HError> {
+        Ok(Form(serde_urlencoded::from_bytes(&body)?))
+    }
+}
+
+impl<V: Send> Deref for Form<V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        &self.0
+    }
+}
+
 pub struct TextPlain(String);
 
 impl TextPlain {
@@ -112,9 +161,6 @@ impl ResponseBody for TextPlain {
     }
 
     fn from_bytes(_: StatusCode, body: Vec<u8>) -> Result<Self, HError> {
-        match String::from_utf8(body) {
-            Ok(s) => Ok(TextPlain(s)),
-            Err(e) => return Err(HError::InvalidDataFormat(format!("{}", e))),
-        }
+        Ok(TextPlain(String::from_utf8(body)?))
     }
 }
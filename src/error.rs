@@ -1,6 +1,11 @@
 use futures;
+use http::StatusCode;
+use hyper;
+use serde_json;
+use serde_urlencoded;
 use std::error::Error;
 use std::fmt;
+use std::string::FromUtf8Error;
 
 pub type HResult<T> = Result<T, HError>;
 pub type RFuture<T> = futures::Future<Item = T, Error = HError> + Send;
@@ -10,6 +15,54 @@ pub enum HError {
     InvalidHttpRequest(String),
     InvalidHttpResponse(String),
     InvalidDataFormat(String),
+    /// Returned when `HttpClient::with_error_for_status()` is enabled and the
+    /// response status is not a 2xx, carrying the raw status and unparsed body
+    /// so callers can inspect the original error payload.
+    UnexpectedStatus { status: StatusCode, body: Vec<u8> },
+    /// Returned when the response body exceeds `HttpClient::max_response_bytes`.
+    BodyTooLarge { limit: usize },
+    /// Returned when a JSON-RPC 2.0 response carries an `"error"` object.
+    RpcError { code: i64, message: String },
+    /// A transport-level failure from the underlying hyper client.
+    Transport(hyper::Error),
+    /// A JSON (de)serialization failure, preserved from `serde_json`.
+    Json(serde_json::Error),
+    /// A body that was expected to be valid UTF-8 wasn't.
+    Utf8(FromUtf8Error),
+    /// A form (`application/x-www-form-urlencoded`) serialization failure.
+    UrlencodedSer(serde_urlencoded::ser::Error),
+    /// A form (`application/x-www-form-urlencoded`) deserialization failure.
+    UrlencodedDe(serde_urlencoded::de::Error),
+}
+
+impl From<hyper::Error> for HError {
+    fn from(e: hyper::Error) -> HError {
+        HError::Transport(e)
+    }
+}
+
+impl From<serde_json::Error> for HError {
+    fn from(e: serde_json::Error) -> HError {
+        HError::Json(e)
+    }
+}
+
+impl From<FromUtf8Error> for HError {
+    fn from(e: FromUtf8Error) -> HError {
+        HError::Utf8(e)
+    }
+}
+
+impl From<serde_urlencoded::ser::Error> for HError {
+    fn from(e: serde_urlencoded::ser::Error) -> HError {
+        HError::UrlencodedSer(e)
+    }
+}
+
+impl From<serde_urlencoded::de::Error> for HError {
+    fn from(e: serde_urlencoded::de::Error) -> HError {
+        HError::UrlencodedDe(e)
+    }
 }
 
 impl Error for HError {
@@ -19,11 +72,35 @@ impl Error for HError {
             HError::InvalidHttpRequest(ref m) => m,
             HError::InvalidHttpResponse(ref m) => m,
             HError::InvalidDataFormat(ref m) => m,
+            HError::UnexpectedStatus { .. } => "unexpected response status",
+            HError::BodyTooLarge { .. } => "response body exceeded the configured size limit",
+            HError::RpcError { ref message, .. } => message,
+            HError::Transport(ref e) => e.description(),
+            HError::Json(ref e) => e.description(),
+            HError::Utf8(ref e) => e.description(),
+            HError::UrlencodedSer(ref e) => e.description(),
+            HError::UrlencodedDe(ref e) => e.description(),
         }
     }
 
     fn cause(&self) -> Option<&Error> {
         match *self {
+            HError::Transport(ref e) => Some(e),
+            HError::Json(ref e) => Some(e),
+            HError::Utf8(ref e) => Some(e),
+            HError::UrlencodedSer(ref e) => Some(e),
+            HError::UrlencodedDe(ref e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            HError::Transport(ref e) => Some(e),
+            HError::Json(ref e) => Some(e),
+            HError::Utf8(ref e) => Some(e),
+            HError::UrlencodedSer(ref e) => Some(e),
+            HError::UrlencodedDe(ref e) => Some(e),
             _ => None,
         }
     }
@@ -32,6 +109,23 @@ impl Error for HError {
 impl fmt::Display for HError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            HError::UnexpectedStatus { status, body } => write!(
+                f,
+                "unexpected response status {} ({} byte body)",
+                status,
+                body.len()
+            ),
+            HError::BodyTooLarge { limit } => {
+                write!(f, "response body exceeded the {} byte limit", limit)
+            }
+            HError::RpcError { code, message } => {
+                write!(f, "jsonrpc error {}: {}", code, message)
+            }
+            HError::Transport(e) => write!(f, "{}", e),
+            HError::Json(e) => write!(f, "{}", e),
+            HError::Utf8(e) => write!(f, "{}", e),
+            HError::UrlencodedSer(e) => write!(f, "{}", e),
+            HError::UrlencodedDe(e) => write!(f, "{}", e),
             _ => write!(f, "{}", self.description()),
         }
     }
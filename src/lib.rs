@@ -8,28 +8,38 @@ extern crate log;
 pub extern crate mime;
 extern crate serde;
 extern crate serde_json;
+extern crate serde_urlencoded;
 extern crate tokio;
 
 use futures::{Future, Stream};
-use http::Request;
+use http::header::{HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use http::{HeaderMap, HttpTryFrom, Request};
 use hyper::Client;
 use hyper::client::HttpConnector;
-use hyper::header::CONTENT_TYPE;
+use mime::Mime;
 use std::convert::From;
-use std::error::Error;
+use std::fmt::Display;
 
 mod error;
 pub mod body;
+pub mod rpc;
 
 pub use error::*;
 pub use http::Uri;
 pub use http::{Method, StatusCode};
 pub use body::*;
+pub use rpc::RpcClient;
 
 pub const DEFAULT_THREAD_NUM: usize = 2;
 
+/// Default ceiling on a response body kept in memory, if the caller doesn't
+/// override it via `HttpClient::max_response_bytes()`.
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
+
 pub struct HttpClient {
     client: Client<HttpConnector>,
+    error_for_status: bool,
+    max_response_bytes: usize,
 }
 
 pub struct Response<T>
@@ -65,9 +75,28 @@ impl HttpClient {
     pub fn new() -> HttpClient {
         HttpClient {
             client: Client::new(),
+            error_for_status: false,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
         }
     }
 
+    /// Enables strict status checking: once set, any response whose status
+    /// is not 2xx short-circuits with `HError::UnexpectedStatus` instead of
+    /// attempting to deserialize the body.
+    pub fn with_error_for_status(mut self) -> HttpClient {
+        self.error_for_status = true;
+        self
+    }
+
+    /// Sets the ceiling on bytes buffered from a response body. Exceeding it
+    /// aborts the in-flight read with `HError::BodyTooLarge` instead of
+    /// continuing to accumulate the body in memory. Defaults to
+    /// `DEFAULT_MAX_RESPONSE_BYTES`.
+    pub fn max_response_bytes(mut self, limit: usize) -> HttpClient {
+        self.max_response_bytes = limit;
+        self
+    }
+
     pub fn get<R: ResponseBody>(
         &self,
         uri: Uri,
@@ -128,24 +157,104 @@ impl HttpClient {
         uri: Uri,
         value: S,
     ) -> Result<impl Future<Item = Response<R>, Error = HError> + 'static, HError>
+    where
+        S: RequestBody + 'static,
+        R: ResponseBody + 'static + Send,
+    {
+        self.request_with(method, uri, value).send()
+    }
+
+    /// Starts building a request with custom headers (e.g. `Authorization`)
+    /// on top of the defaults `HttpClient` would otherwise send. Call
+    /// `.header(..)` / `.bearer_auth(..)` to set headers, then `.send()`.
+    pub fn request_with<S>(&self, method: Method, uri: Uri, value: S) -> RequestBuilder<S>
+    where
+        S: RequestBody + 'static,
+    {
+        RequestBuilder {
+            client: self,
+            method,
+            uri,
+            value,
+            headers: HeaderMap::new(),
+            error: None,
+        }
+    }
+
+    fn request_raw<S, R>(
+        &self,
+        method: Method,
+        uri: Uri,
+        value: S,
+        headers: HeaderMap,
+    ) -> Result<impl Future<Item = Response<R>, Error = HError> + 'static, HError>
     where
         S: RequestBody + 'static,
         R: ResponseBody + 'static + Send,
     {
         debug!("{} {} ({})", &method, &uri, S::MIME.as_ref());
+        let mut merged = HeaderMap::new();
+        merged.insert(CONTENT_TYPE, HeaderValue::from_str(S::MIME.as_ref())
+            .map_err(|e| HError::InvalidHttpRequest(format!("{}", e)))?);
+        for (name, value) in headers.iter() {
+            merged.insert(name.clone(), value.clone());
+        }
         let mut builder = Request::builder();
-        let req = match builder
-            .uri(uri.clone())
-            .method(method)
-            .header(CONTENT_TYPE, S::MIME.as_ref())
-            .body(hyper::Body::from(value.to_bytes()?))
-        {
+        builder.uri(uri.clone()).method(method);
+        for (name, value) in merged.iter() {
+            builder.header(name, value);
+        }
+        let req = match builder.body(hyper::Body::from(value.to_bytes()?)) {
             Ok(req) => req,
             Err(e) => return Err(HError::InvalidHttpRequest(format!("{}", e))),
         };
         Ok(self.handle_response(req))
     }
 
+    /// Like `get`, but returns the status alongside the body as a stream of
+    /// chunks instead of buffering the whole response in memory. Callers can
+    /// consume it incrementally or pipe it to disk.
+    pub fn get_stream(
+        &self,
+        uri: Uri,
+    ) -> impl Future<
+        Item = (StatusCode, Box<Stream<Item = Vec<u8>, Error = HError> + Send>),
+        Error = HError,
+    > + 'static {
+        debug!("GET {} ({}) [stream]", &uri, "*/*");
+        let mut builder = Request::builder();
+        let req = builder
+            .uri(uri.clone())
+            .method(Method::GET)
+            .header(CONTENT_TYPE, "text/plain")
+            .body(hyper::Body::default())
+            .expect("http::Builder::body() failed");
+        self.stream_response(req)
+    }
+
+    fn stream_response(
+        &self,
+        req: Request<hyper::Body>,
+    ) -> impl Future<
+        Item = (StatusCode, Box<Stream<Item = Vec<u8>, Error = HError> + Send>),
+        Error = HError,
+    > + 'static {
+        self.client.request(req).then(
+            |result: Result<http::Response<hyper::Body>, hyper::Error>| match result {
+                Ok(r) => {
+                    let status = r.status();
+                    let stream: Box<Stream<Item = Vec<u8>, Error = HError> + Send> = Box::new(
+                        r.into_body()
+                            .map_err(HError::from)
+                            .map(|chunk| chunk.to_vec()),
+                    );
+                    Ok((status, stream))
+                }
+                Err(e) => Err(HError::from(e)),
+            },
+        )
+    }
+
     fn handle_response<R>(
         &self,
         req: Request<hyper::Body>,
@@ -153,36 +262,124 @@ impl HttpClient {
     where
         R: ResponseBody + 'static + Send,
     {
+        let error_for_status = self.error_for_status;
+        let max_response_bytes = self.max_response_bytes;
         self.client
             .request(req)
             .then(
-                |result: Result<http::Response<hyper::Body>, hyper::Error>| -> Result<
+                move |result: Result<http::Response<hyper::Body>, hyper::Error>| -> Result<
                     Box<Future<Item = Response<R>, Error = HError> + 'static + Send>,
                     HError,
                 > {
                     match result {
                         Ok(r) => {
                             let status_code = r.status();
+                            let content_type: Option<Mime> = r
+                                .headers()
+                                .get(CONTENT_TYPE)
+                                .and_then(|v| v.to_str().ok())
+                                .and_then(|s| s.parse::<Mime>().ok());
                             let future = Box::new(
                                 r.into_body()
-                                    .map_err(|e| {
-                                        HError::InvalidHttpResponse(e.description().to_owned())
-                                    })
-                                    .fold(Vec::new(), |mut acc, chunk| {
+                                    .map_err(HError::from)
+                                    .fold(Vec::new(), move |mut acc, chunk| {
+                                        if acc.len() + chunk.len() > max_response_bytes {
+                                            return futures::future::err(HError::BodyTooLarge {
+                                                limit: max_response_bytes,
+                                            });
+                                        }
                                         acc.extend_from_slice(&*chunk);
                                         futures::future::ok::<_, HError>(acc)
                                     })
                                     .and_then(move |body| {
+                                        if error_for_status && !status_code.is_success() {
+                                            return Err(HError::UnexpectedStatus {
+                                                status: status_code,
+                                                body,
+                                            });
+                                        }
+                                        if !R::content_type_matches(content_type.as_ref()) {
+                                            return Err(HError::InvalidHttpResponse(format!(
+                                                "unexpected content-type {:?}, expected {}",
+                                                content_type,
+                                                R::accept_types()
+                                            )));
+                                        }
                                         R::from_bytes(status_code, body)
                                             .map(|payload| Response::new(status_code, payload))
                                     }),
                             );
                             Ok(future)
                         }
-                        Err(e) => Err(HError::InvalidHttpResponse(format!("{}", e))),
+                        Err(e) => Err(HError::from(e)),
                     }
                 },
             )
             .and_then(|res| res)
     }
 }
+
+/// Builds a single request on top of an `HttpClient`, letting callers add
+/// headers (e.g. `Authorization`, `User-Agent`) before sending it. User
+/// headers set here are merged over the client's defaults, replacing them by
+/// name. Obtained from `HttpClient::request_with()`.
+pub struct RequestBuilder<'a, S> {
+    client: &'a HttpClient,
+    method: Method,
+    uri: Uri,
+    value: S,
+    headers: HeaderMap,
+    error: Option<HError>,
+}
+
+impl<'a, S> RequestBuilder<'a, S>
+where
+    S: RequestBody + 'static,
+{
+    /// Sets a header, replacing any previous value set for the same name. An
+    /// invalid name or value is not a panic: it's recorded and returned from
+    /// `send()` as `HError::InvalidHttpRequest`.
+    pub fn header<K, V>(mut self, name: K, value: V) -> RequestBuilder<'a, S>
+    where
+        HeaderName: HttpTryFrom<K>,
+        HeaderValue: HttpTryFrom<V>,
+    {
+        if self.error.is_some() {
+            return self;
+        }
+        let name = match HeaderName::try_from(name) {
+            Ok(name) => name,
+            Err(_) => {
+                self.error = Some(HError::InvalidHttpRequest("invalid header name".to_owned()));
+                return self;
+            }
+        };
+        let value = match HeaderValue::try_from(value) {
+            Ok(value) => value,
+            Err(_) => {
+                self.error = Some(HError::InvalidHttpRequest(
+                    "invalid header value".to_owned(),
+                ));
+                return self;
+            }
+        };
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Sets `Authorization: Bearer <token>`.
+    pub fn bearer_auth<T: Display>(self, token: T) -> RequestBuilder<'a, S> {
+        self.header(AUTHORIZATION, format!("Bearer {}", token))
+    }
+
+    pub fn send<R>(self) -> Result<impl Future<Item = Response<R>, Error = HError> + 'static, HError>
+    where
+        R: ResponseBody + 'static + Send,
+    {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.client
+            .request_raw(self.method, self.uri, self.value, self.headers)
+    }
+}
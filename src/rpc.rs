@@ -0,0 +1,82 @@
+//! A minimal JSON-RPC 2.0 client layered on top of `HttpClient`.
+
+use body::Json;
+use error::HError;
+use futures::Future;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Map, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use {HttpClient, Uri};
+
+/// Calls JSON-RPC 2.0 methods over an `HttpClient`, assigning each request a
+/// monotonically increasing id and unwrapping the `result`/`error` envelope.
+/// The target `uri` is passed per call, so one `RpcClient` can talk to
+/// multiple endpoints through the same underlying `HttpClient`.
+pub struct RpcClient {
+    http: HttpClient,
+    next_id: AtomicUsize,
+}
+
+impl RpcClient {
+    pub fn new(http: HttpClient) -> RpcClient {
+        RpcClient {
+            http,
+            next_id: AtomicUsize::new(1),
+        }
+    }
+
+    pub fn call<P, R>(
+        &self,
+        uri: Uri,
+        method: &str,
+        params: P,
+    ) -> Result<impl Future<Item = R, Error = HError> + 'static, HError>
+    where
+        P: Serialize,
+        R: DeserializeOwned + 'static + Send,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) as u64;
+        let params = serde_json::to_value(&params)?;
+
+        let mut envelope = Map::new();
+        envelope.insert("jsonrpc".to_owned(), Value::String("2.0".to_owned()));
+        envelope.insert("method".to_owned(), Value::String(method.to_owned()));
+        envelope.insert("params".to_owned(), params);
+        envelope.insert("id".to_owned(), Value::from(id));
+
+        let future = self
+            .http
+            .post::<_, Json<Value>>(uri, Json(Value::Object(envelope)))?
+            .and_then(move |res| {
+                let body = res.into_inner().into_inner();
+
+                if let Some(error) = body.get("error") {
+                    // Per the JSON-RPC 2.0 spec, an error response may carry
+                    // `"id": null` (e.g. for Parse/Invalid-Request errors), so
+                    // the id is not checked here.
+                    let code = error.get("code").and_then(Value::as_i64).unwrap_or(0);
+                    let message = error
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or("")
+                        .to_owned();
+                    return Err(HError::RpcError { code, message });
+                }
+
+                if body.get("id").and_then(Value::as_u64) != Some(id) {
+                    return Err(HError::InvalidDataFormat(
+                        "jsonrpc response id did not match the request id".to_owned(),
+                    ));
+                }
+
+                match body.get("result") {
+                    Some(result) => Ok(serde_json::from_value(result.clone())?),
+                    None => Err(HError::InvalidDataFormat(
+                        "jsonrpc response had neither result nor error".to_owned(),
+                    )),
+                }
+            });
+        Ok(future)
+    }
+}